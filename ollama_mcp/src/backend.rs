@@ -0,0 +1,33 @@
+use anyhow::Result;
+
+/// Common surface every embedding/LLM provider exposes to the RAG pipeline.
+///
+/// [`crate::OllamaMCP`] implements this against a local Ollama-style HTTP API;
+/// `rag_gemini`'s `GeminiBackend` implements it against the Gemini API. Code that
+/// ingests and retrieves documents is written once against `Backend` so switching
+/// providers doesn't mean rewriting the chunking/retrieval pipeline.
+///
+/// `Send + Sync` is required because [`crate::RAGSystem`] fans chunk embedding out
+/// across a rayon thread pool, which needs to share `&self` across worker threads.
+pub trait Backend: Send + Sync {
+    /// Generates an embedding vector for `content`.
+    fn generate_embedding(&self, content: &str) -> Result<Vec<f32>>;
+
+    /// Sends `query` to the provider's completion endpoint and returns its response.
+    fn query(&self, query: &str) -> Result<String>;
+
+    /// Extracts text from a document at `path`, if the provider supports remote
+    /// extraction. Providers that only do local extraction (e.g. via `pdf_extract`)
+    /// can leave this unimplemented.
+    fn extract_text(&self, _path: &str) -> Result<String> {
+        anyhow::bail!("This backend does not support remote text extraction")
+    }
+
+    /// How many threads [`crate::RAGSystem`] should use to fan out bulk-ingest
+    /// embedding requests for this backend. `None` leaves `RAGSystem` at its own
+    /// default; backends with a configurable thread count (e.g. `OllamaMCP`'s
+    /// `EmbedderOptions::thread_count`) should return it here.
+    fn preferred_thread_count(&self) -> Option<usize> {
+        None
+    }
+}
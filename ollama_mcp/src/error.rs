@@ -0,0 +1,86 @@
+use thiserror::Error;
+
+/// Typed failures from calls to the Ollama HTTP API, distinguishing caller mistakes
+/// (bad model name, missing credentials, malformed request) from server/infrastructure
+/// problems so downstream callers can surface an actionable message instead of a
+/// generic panic on `.unwrap()`.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("model not found: {0}")]
+    ModelNotFound(String),
+
+    #[error("unauthorized: check the Ollama API key")]
+    Unauthorized,
+
+    #[error("bad request to the Ollama API (status {0})")]
+    BadRequest(u16),
+
+    #[error("Ollama server returned a transient error (status {0})")]
+    Transient(u16),
+
+    #[error("could not reach the Ollama server at the configured URL")]
+    ServerUnreachable,
+
+    #[error("response missing expected field '{field}'")]
+    MissingField { field: &'static str },
+
+    #[error("failed to deserialize Ollama API response: {0}")]
+    Deserialize(#[from] serde_json::Error),
+}
+
+impl Error {
+    /// Whether this failure is something the caller can fix (wrong model, bad
+    /// credentials, malformed request) as opposed to an infrastructure problem on
+    /// the server side.
+    pub fn is_client_fault(&self) -> bool {
+        matches!(self, Error::ModelNotFound(_) | Error::Unauthorized | Error::BadRequest(_))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Maps a non-success HTTP status from the Ollama API into a typed [`Error`].
+/// `429`/`5xx` are transient and worth retrying; `404`/`401` are specific client
+/// mistakes; everything else (e.g. `400`) is treated as a non-retryable bad request.
+pub(crate) fn from_status(status: reqwest::StatusCode, model_name: &str) -> Error {
+    match status.as_u16() {
+        404 => Error::ModelNotFound(model_name.to_string()),
+        401 => Error::Unauthorized,
+        code @ (429 | 500 | 502 | 503 | 504) => Error::Transient(code),
+        code => Error::BadRequest(code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn maps_known_client_errors() {
+        assert!(matches!(from_status(StatusCode::NOT_FOUND, "llama3"), Error::ModelNotFound(m) if m == "llama3"));
+        assert!(matches!(from_status(StatusCode::UNAUTHORIZED, "llama3"), Error::Unauthorized));
+    }
+
+    #[test]
+    fn maps_transient_server_errors() {
+        for code in [429, 500, 502, 503, 504] {
+            let status = StatusCode::from_u16(code).unwrap();
+            assert!(matches!(from_status(status, "llama3"), Error::Transient(c) if c == code));
+        }
+    }
+
+    #[test]
+    fn maps_bad_request_as_non_retryable() {
+        assert!(matches!(from_status(StatusCode::BAD_REQUEST, "llama3"), Error::BadRequest(400)));
+    }
+
+    #[test]
+    fn client_faults_are_flagged() {
+        assert!(Error::ModelNotFound("x".to_string()).is_client_fault());
+        assert!(Error::Unauthorized.is_client_fault());
+        assert!(Error::BadRequest(400).is_client_fault());
+        assert!(!Error::Transient(500).is_client_fault());
+        assert!(!Error::ServerUnreachable.is_client_fault());
+    }
+}
@@ -0,0 +1,10 @@
+mod backend;
+mod error;
+mod rag;
+mod retry;
+mod utils;
+
+pub use crate::backend::Backend;
+pub use crate::error::Error;
+pub use crate::rag::{clean_text, RAGSystem};
+pub use crate::utils::ollama::{EmbedderOptions, OllamaMCP};
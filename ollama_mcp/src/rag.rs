@@ -0,0 +1,278 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::backend::Backend;
+
+/// Target chunk size in words (roughly tokens) when splitting documents for embedding.
+const CHUNK_SIZE_WORDS: usize = 500;
+/// Overlap between consecutive chunks, in words, so a match near a chunk boundary isn't lost.
+const CHUNK_OVERLAP_WORDS: usize = 50;
+/// How many top-scoring chunks to concatenate into the retrieved context.
+const TOP_K: usize = 3;
+/// Cosine-similarity floor below which a chunk is considered irrelevant.
+const MIN_SCORE: f32 = 0.5;
+/// Default number of threads used to fan out embedding requests during bulk ingest.
+const DEFAULT_EMBED_THREADS: usize = 4;
+
+/// Splits `content` into overlapping word-windows so each chunk stays under the
+/// embedding model's context limit while preserving context across boundaries.
+fn chunk_text(content: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<&str> = content.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let stride = chunk_size.saturating_sub(overlap).max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < words.len() {
+        let end = (start + chunk_size).min(words.len());
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+        start += stride;
+    }
+    chunks
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Collapses runs of whitespace in `content` down to single spaces and trims the
+/// ends. Exposed publicly so callers (e.g. `rag_gemini`'s IC query surface) can
+/// reuse the exact normalization `RAGSystem::add_pdf_document` applies internally.
+pub fn clean_text(content: &str) -> String {
+    let re = Regex::new(r"\s+").unwrap(); //regex of multiple spaces
+    re.replace_all(content, " ").trim().to_string()
+}
+
+/// Chunking, embedding, and cosine-similarity retrieval pipeline, written once and
+/// shared by every [`Backend`] implementation (`OllamaMCP`, `GeminiBackend`, ...)
+/// instead of each provider re-implementing its own ingest/retrieve logic.
+pub struct RAGSystem<B: Backend> {
+    backend: B,
+    document_store: HashMap<String, String>,
+    processed_text_path: Option<String>,
+    /// (doc_id, chunk_text, embedding) index used for semantic retrieval.
+    chunks: Vec<(String, String, Vec<f32>)>,
+    /// How many threads [`RAGSystem::embed_chunks`] fans bulk-ingest embedding
+    /// requests out across.
+    thread_count: usize,
+}
+
+impl<B: Backend> RAGSystem<B> {
+    pub fn new(backend: B) -> Self {
+        let thread_count = backend.preferred_thread_count().unwrap_or(DEFAULT_EMBED_THREADS);
+        Self {
+            backend,
+            document_store: HashMap::new(),
+            processed_text_path: None,
+            chunks: Vec::new(),
+            thread_count,
+        }
+    }
+
+    pub fn with_thread_count(mut self, thread_count: usize) -> Self {
+        self.thread_count = thread_count;
+        self
+    }
+
+    /// The backend this pipeline is ingesting/retrieving through, e.g. to send the
+    /// retrieved context on to [`Backend::query`] for a completion.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn add_document(&mut self, doc_id: &str, content: &str) -> Result<()> {
+        for chunk in chunk_text(content, CHUNK_SIZE_WORDS, CHUNK_OVERLAP_WORDS) {
+            let embedding = self
+                .backend
+                .generate_embedding(&chunk)
+                .context("Failed to generate embedding for document chunk")?;
+            self.chunks.push((doc_id.to_string(), chunk, embedding));
+        }
+
+        self.document_store.insert(doc_id.to_string(), content.to_string());
+        println!("Document added with ID {}", doc_id);
+        Ok(())
+    }
+
+    pub fn add_pdf_document(&mut self, pdf_path: &str, output_path: &str) -> Result<()> {
+        let content = match self.backend.extract_text(pdf_path) {
+            Ok(text) => text,
+            Err(_) => pdf_extract::extract_text(pdf_path)
+                .context(format!("Failed to extract text from PDF: {}", pdf_path))?,
+        };
+
+        if content.is_empty() {
+            eprintln!("Warning: Extracted content is empty.");
+        } else {
+            println!("Extracted {} characters from PDF", content.len());
+        }
+
+        let cleaned_content = clean_text(&content);
+
+        let mut file = BufWriter::new(File::create(output_path)
+            .context(format!("Failed to create output file: {}", output_path))?);
+        file.write_all(cleaned_content.as_bytes())
+            .context("Failed to write extracted text to file")?;
+
+        println!("Extracted content saved to {}", output_path);
+
+        self.processed_text_path = Some(output_path.to_string());
+        self.document_store.insert(pdf_path.to_string(), cleaned_content.clone());
+
+        let chunk_texts = chunk_text(&cleaned_content, CHUNK_SIZE_WORDS, CHUNK_OVERLAP_WORDS);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.thread_count)
+            .build()
+            .context("Failed to build embedding thread pool")?;
+        let embedded = self
+            .embed_chunks(chunk_texts, &pool)
+            .context("Failed to embed PDF chunks concurrently")?;
+
+        for (chunk, embedding) in embedded {
+            self.chunks.push((pdf_path.to_string(), chunk, embedding));
+        }
+
+        Ok(())
+    }
+
+    /// Embeds `chunks` concurrently across `pool`, bounded by its configured thread
+    /// count, returning each chunk paired with its embedding in the same order as
+    /// the input.
+    fn embed_chunks(&self, chunks: Vec<String>, pool: &rayon::ThreadPool) -> Result<Vec<(String, Vec<f32>)>> {
+        pool.install(|| {
+            chunks
+                .into_par_iter()
+                .map(|chunk| {
+                    let embedding = self.backend.generate_embedding(&chunk)?;
+                    Ok((chunk, embedding))
+                })
+                .collect()
+        })
+    }
+
+    /// Retrieves the most semantically relevant chunks for `query` by embedding it and
+    /// ranking every stored chunk by cosine similarity. Returns the top matches
+    /// concatenated in descending score order, or an empty string below `MIN_SCORE`.
+    pub fn retrieve(&self, query: &str) -> Result<String> {
+        if self.chunks.is_empty() {
+            println!("WARNING: No documents have been indexed yet!");
+            return Ok(String::new());
+        }
+
+        let query_embedding = self
+            .backend
+            .generate_embedding(query)
+            .context("Failed to embed query for retrieval")?;
+
+        let mut scored: Vec<(f32, &str)> = self
+            .chunks
+            .iter()
+            .map(|(_, chunk, embedding)| (cosine_similarity(&query_embedding, embedding), chunk.as_str()))
+            .filter(|(score, _)| *score >= MIN_SCORE)
+            .collect();
+
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+        if scored.is_empty() {
+            println!("No relevant content found for query: \"{}\"", query);
+            return Ok(String::new());
+        }
+
+        let context = scored
+            .into_iter()
+            .take(TOP_K)
+            .map(|(_, chunk)| chunk)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_text_splits_on_word_boundaries_with_overlap() {
+        let words: Vec<String> = (0..12).map(|i| i.to_string()).collect();
+        let content = words.join(" ");
+
+        let chunks = chunk_text(&content, 5, 2);
+
+        assert_eq!(chunks[0], "0 1 2 3 4");
+        assert_eq!(chunks[1], "3 4 5 6 7");
+        assert_eq!(*chunks.last().unwrap(), "9 10 11");
+    }
+
+    #[test]
+    fn chunk_text_empty_input_yields_no_chunks() {
+        assert!(chunk_text("", 500, 50).is_empty());
+        assert!(chunk_text("   ", 500, 50).is_empty());
+    }
+
+    #[test]
+    fn chunk_text_shorter_than_chunk_size_is_one_chunk() {
+        let chunks = chunk_text("a b c", 500, 50);
+        assert_eq!(chunks, vec!["a b c".to_string()]);
+    }
+
+    #[test]
+    fn cosine_similarity_identical_vectors_is_one() {
+        let v = vec![1.0, 2.0, 3.0];
+        assert!((cosine_similarity(&v, &v) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cosine_similarity_orthogonal_vectors_is_zero() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn cosine_similarity_zero_vector_is_zero() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+
+    /// Minimal `Backend` whose embedding is just the chunk's length, so multi-chunk
+    /// output can be checked without a real HTTP call.
+    struct FakeBackend;
+
+    impl Backend for FakeBackend {
+        fn generate_embedding(&self, content: &str) -> Result<Vec<f32>> {
+            Ok(vec![content.len() as f32])
+        }
+
+        fn query(&self, query: &str) -> Result<String> {
+            Ok(query.to_string())
+        }
+    }
+
+    #[test]
+    fn embed_chunks_pairs_each_chunk_with_its_embedding_on_the_pool() {
+        let rag = RAGSystem::new(FakeBackend);
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+        let chunks = vec!["a".to_string(), "bb".to_string(), "ccc".to_string()];
+
+        let embedded = rag.embed_chunks(chunks, &pool).unwrap();
+
+        assert_eq!(embedded.len(), 3);
+        assert!(embedded.contains(&("a".to_string(), vec![1.0])));
+        assert!(embedded.contains(&("bb".to_string(), vec![2.0])));
+        assert!(embedded.contains(&("ccc".to_string(), vec![3.0])));
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::Error;
+
+/// Ceiling on the backoff delay between attempts, regardless of how many times
+/// `base_delay` has doubled.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Runs `attempt` up to `max_attempts` times, retrying with exponential backoff and
+/// jitter on transient failures (a server that's unreachable or still warming up),
+/// but giving up immediately on errors that a retry can't fix (bad model, bad
+/// credentials).
+pub(crate) fn with_backoff<T>(
+    max_attempts: u32,
+    base_delay: Duration,
+    mut attempt: impl FnMut() -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut delay = base_delay;
+
+    for attempt_num in 1..=max_attempts.max(1) {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt_num < max_attempts && is_retryable(&err) => {
+                let jitter_ms = rand::thread_rng().gen_range(0..=delay.as_millis() as u64 / 2 + 1);
+                std::thread::sleep(delay + Duration::from_millis(jitter_ms));
+                delay = (delay * 2).min(MAX_DELAY);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    unreachable!("the loop above always returns on its final iteration")
+}
+
+/// Only infrastructure-side failures are worth retrying; a caller mistake (bad
+/// model name, bad credentials, bad request, malformed response) will fail the
+/// same way again.
+fn is_retryable(err: &Error) -> bool {
+    matches!(err, Error::ServerUnreachable | Error::Transient(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_transient_failures_until_success() {
+        let mut calls = 0;
+        let result = with_backoff(3, Duration::from_millis(1), || {
+            calls += 1;
+            if calls < 3 {
+                Err(Error::Transient(503))
+            } else {
+                Ok("ok")
+            }
+        });
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn gives_up_immediately_on_non_retryable_errors() {
+        let mut calls = 0;
+        let result = with_backoff(5, Duration::from_millis(1), || {
+            calls += 1;
+            Err::<(), _>(Error::Unauthorized)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn stops_after_max_attempts() {
+        let mut calls = 0;
+        let result = with_backoff(3, Duration::from_millis(1), || {
+            calls += 1;
+            Err::<(), _>(Error::ServerUnreachable)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn is_retryable_matches_transient_and_unreachable_only() {
+        assert!(is_retryable(&Error::ServerUnreachable));
+        assert!(is_retryable(&Error::Transient(500)));
+        assert!(!is_retryable(&Error::BadRequest(400)));
+        assert!(!is_retryable(&Error::Unauthorized));
+        assert!(!is_retryable(&Error::ModelNotFound("x".to_string())));
+    }
+}
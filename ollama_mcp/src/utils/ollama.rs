@@ -1,235 +1,242 @@
 use anyhow::{Context, Result};
 use reqwest::blocking::Client;
-use serde_json::Value; 
-use std::collections::HashMap;
-use std::fs::{create_dir_all, File};
-use std::io::{BufWriter, Write};
-use pdf_extract::extract_text;
-
-
-
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::backend::Backend;
+use crate::error::{self, Error};
+use crate::retry;
+
+/// The probe word embedded once at construction time to auto-detect a model's
+/// output dimensionality when the caller doesn't already know it.
+const DIMENSION_PROBE_TEXT: &str = "test";
+
+/// Default number of threads used to fan out embedding requests during bulk ingest.
+const DEFAULT_EMBED_THREADS: usize = 4;
+/// Default context window passed as `num_ctx`, since Ollama exposes no API to read
+/// a model's actual max tokens.
+const DEFAULT_NUM_CTX: usize = 4096;
+/// Default number of attempts before a transient failure is given up on.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+/// Default base delay for exponential backoff between retries.
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Configuration for the embedding model and query behavior backing an [`OllamaMCP`]
+/// instance.
+///
+/// `dimensions` may be left at `0`, in which case [`OllamaMCP::new`] calibrates it
+/// automatically by embedding [`DIMENSION_PROBE_TEXT`] once and recording the
+/// returned vector length. Every embedding generated afterwards is checked against
+/// that length so mismatched models can't silently corrupt the document store.
+/// `thread_count` bounds how many chunk-embedding requests [`crate::RAGSystem`]
+/// fans out concurrently against this backend, so hosts running a local Ollama
+/// server can throttle load. `num_ctx` is sent with every query so long retrieved
+/// contexts aren't silently truncated by the server. `max_attempts` and
+/// `base_delay` control how the embedding, query and PDF-extraction calls retry
+/// with exponential backoff against a model that is still loading; `list_models`
+/// makes a single attempt.
+pub struct EmbedderOptions {
+    pub model_name: String,
+    pub dimensions: usize,
+    pub thread_count: usize,
+    pub num_ctx: usize,
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
 
+impl EmbedderOptions {
+    pub fn new(model_name: impl Into<String>) -> Self {
+        Self {
+            model_name: model_name.into(),
+            dimensions: 0,
+            thread_count: DEFAULT_EMBED_THREADS,
+            num_ctx: DEFAULT_NUM_CTX,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+        }
+    }
+}
 
+/// OllamaMCP is a thin [`Backend`] over the Ollama HTTP API: it generates
+/// embeddings, answers queries, and extracts PDF text remotely. Chunking, the
+/// document/embedding store, and retrieval live once in [`crate::RAGSystem`],
+/// shared by every `Backend` implementation instead of being reimplemented here.
 pub struct OllamaMCP {
-    """
-    OllamaMCP is a system for managing and processing documents using the Ollama API.
-    It provides functionality to add documents, extract text from PDFs, generate embeddings,
-    and retrieve relevant content based on queries.
-
-    Attributes:
-    - document_store: A HashMap to store documents with their IDs as keys.
-    - processed_text_path: An optional path to the processed text file.
-    - OLLAMA_API_URL: The URL of the Ollama API.
-
-    Methods:
-    - new: Initializes the OllamaMCP system with an empty document store and API credentials.
-    - add_document: Adds a document to the system and generates an embedding for it.
-    - add_pdf_document: Adds a PDF document to the system, extracts text, and saves it to a file.
-    - retrieve: Retrieves relevant content based on the query using the Ollama API.
-    - extract_text_from_pdf: Extracts text from a PDF file using the Ollama API.
-    - generate_embedding: Generates an embedding for the given content using the Ollama API.
-    - query_ollama: Sends a query to the Ollama API and retrieves the response.
-    - clean_text: Cleans the extracted text by removing extra spaces.
-
-    INCOMING FUNCTIONS:
-    - convert_pdf_to_images: Converts a PDF file to images and saves them to a specified directory.
-    - process_pdf_images: Processes the images extracted from the PDF and saves them to a specified directory.
-    - process_pdf: Processes the PDF file by extracting text and converting it to images.
-    """
-    document_store: HashMap<String, String>,
-    processed_text_path: Option<String>,
     ollama_api_url: String,
+    ollama_api_key: String,
+    embedder: EmbedderOptions,
 }
 
 impl OllamaMCP {
-    pub fn new(ollama_api_url: &str, ollama_api_key: &str) -> Self {
-        """
-        Initializes the OllamaMCP system with an empty document store and Local API credentials.
-
-        Input:
-        - ollama_api_url: The URL of the Ollama API.
-        - ollama_api_key: The API key for authentication.
-        Output:
-        - Self: An instance of the OllamaMCP system.
-
-        Note: The document store is a HashMap that will hold the documents added to the system.
-        """
-        Self {
-            document_store: HashMap::new(),
-            processed_text_path: None,
-            ollama_api_url: ollama_api_url.to_string()
+    /// Initializes the OllamaMCP backend with the given API credentials. If
+    /// `embedder.dimensions` is `0`, it is calibrated by embedding a probe word
+    /// once so later embeddings can be validated for consistent width.
+    pub fn new(ollama_api_url: &str, ollama_api_key: &str, mut embedder: EmbedderOptions) -> Result<Self> {
+        let mut mcp = Self {
+            ollama_api_url: ollama_api_url.to_string(),
+            ollama_api_key: ollama_api_key.to_string(),
+            embedder: EmbedderOptions {
+                model_name: std::mem::take(&mut embedder.model_name),
+                dimensions: embedder.dimensions,
+                thread_count: embedder.thread_count,
+                num_ctx: embedder.num_ctx,
+                max_attempts: embedder.max_attempts,
+                base_delay: embedder.base_delay,
+            },
+        };
+
+        mcp.list_models()
+            .context("Failed to reach the Ollama server — is Ollama running?")?;
+
+        if mcp.embedder.dimensions == 0 {
+            let probe = mcp
+                .generate_embedding_raw(DIMENSION_PROBE_TEXT)
+                .context("Failed to auto-detect embedding dimensionality")?;
+            mcp.embedder.dimensions = probe.len();
         }
-    }
 
-    pub fn add_document(&mut self, doc_id: &str, content: &str) {
-        """
-        Adds a document to the system and generates an embedding for it.
-        The document is stored in the document store with the given ID.
-
-        Input:
-        - doc_id: Unique identifier for the document.
-        - content: The content of the document to be added.
-        Output:
-        - Result: Ok if successful, Err if there was an error.
-
-        Note: The embedding is generated using the Ollama API.
-        """
-        let embedding = self.generate_embedding(content).context("Failed to generate embedding through ollama embedding model")?;
-        self.document_store.insert(doc_id.to_string(), content.to_string());
-        println!("Document added with ID", doc_id);
-        Ok(())
+        Ok(mcp)
     }
 
-    pub fn add_pdf_document(&mut self, pdf_path: &str, output_path: &str) -> Result<()> {
-        """
-        Adds a PDF document to the system, extracts text, and saves it to a file.
-        The extracted text is also added to the document store.
-
-        Input:
-        - pdf_path: Path to the PDF file to be processed.
-        - output_path: Path to save the extracted text file.
-        Output:
-        - Result: Ok if successful, Err if there was an error.
-
-        Note: The extracted text is saved to the specified output path.
-        """
-        let extracted_text = self.extract_text_from_pdf(pdf_path)
-            .context(format!("Failed to extract text from PDF: {}", pdf_path))?;
-
-        if extracted_text.is_empty() {
-            eprintln!("Warning: Extracted content is empty.");
-        } else {
-            println!("Extracted {} characters from PDF", extracted_text.len());
-        }
+    /// Lists the models available on the Ollama server. A successful response also
+    /// serves as a health check: there is no dedicated health endpoint, so a
+    /// reachable `/tags` response is the signal that the server is actually running.
+    pub fn list_models(&self) -> error::Result<Vec<String>> {
+        let client = Client::new();
+        let response = client
+            .get(format!("{}/tags", self.ollama_api_url))
+            .header("Authorization", format!("Bearer {}", self.ollama_api_key))
+            .send()
+            .map_err(|_| Error::ServerUnreachable)?;
 
-        let mut file = BufWriter::new(File::create(output_path)
-            .context(format!("Failed to create output file: {}", output_path))?);
-        file.write_all(extracted_text.as_bytes())
-            .context("Failed to write extracted text to file")?;
+        if !response.status().is_success() {
+            return Err(error::from_status(response.status(), &self.embedder.model_name));
+        }
 
-        println!("Extracted content saved to {}", output_path);
+        let response_text = response.text().map_err(|_| Error::ServerUnreachable)?;
+        let body: Value = serde_json::from_str(&response_text)?;
 
-        self.processed_text_path = Some(output_path.to_string());
-        self.add_document(pdf_path, &extracted_text)?;
-        Ok(())
+        body["models"]
+            .as_array()
+            .map(|models| {
+                models
+                    .iter()
+                    .filter_map(|m| m["name"].as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .ok_or(Error::MissingField { field: "models" })
     }
 
+    /// Extracts text from a PDF file via the Ollama API, retrying transient failures
+    /// (e.g. the model is still loading) with exponential backoff.
+    fn extract_text_from_pdf(&self, pdf_path: &str) -> error::Result<String> {
+        retry::with_backoff(self.embedder.max_attempts, self.embedder.base_delay, || {
+            let client = Client::new();
+            let response = client
+                .post(format!("{}/extract-pdf", self.ollama_api_url))
+                .json(&serde_json::json!({ "pdf_path": pdf_path }))
+                .send()
+                .map_err(|_| Error::ServerUnreachable)?;
+
+            if !response.status().is_success() {
+                return Err(error::from_status(response.status(), &self.embedder.model_name));
+            }
+
+            let response_text = response.text().map_err(|_| Error::ServerUnreachable)?;
+            let content: Value = serde_json::from_str(&response_text)?;
+
+            content["text"]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or(Error::MissingField { field: "text" })
+        })
+    }
 
-    pub fn retrieve(&self, query: &str) -> Result<String> {
-        """
-        Retrieves relevant content based on the query using the Ollama API.
-        If no relevant content is found, it returns an empty string.
-
-        Input:
-        - query: The query string to search for relevant content.
-        Output:
-        - Result: Ok with the relevant content if found, Err if there was an error.
-
-        Note: The query is sent to the Ollama API, and the response is processed.
-        """
-        let response = self.query_ollama(query)
-            .context("Failed to query Ollama for relevant content")?;
-
-        if response.is_empty() {
-            println!("No relevant content found for query: \"{}\"", query);
-            return Ok(String::new());
+    /// Generates an embedding for `content` and asserts it matches the
+    /// dimensionality calibrated in [`OllamaMCP::new`].
+    fn generate_embedding(&self, content: &str) -> Result<Vec<f32>> {
+        let embedding = self.generate_embedding_raw(content)?;
+
+        if embedding.len() != self.embedder.dimensions {
+            anyhow::bail!(
+                "Embedding dimension mismatch for model '{}': expected {} dims, got {}",
+                self.embedder.model_name,
+                self.embedder.dimensions,
+                embedding.len()
+            );
         }
 
-        println!("Query result: {}", response);
-        Ok(response)
+        Ok(embedding)
     }
 
-    fn extract_text_from_pdf(&self, pdf_path: &str) -> Result<String> {
-        """
-        Extracts text from a PDF file using the local function. (pdf_extract::extract_text)
-
-        Input:
-        - pdf_path: Path to the PDF file to be processed.
-        Output:
-        - Result: Ok with the extracted text if successful, Err if there was an error.
-
-        """
-        let client = Client::new();
-        let response = client
-            .post(format!("{}/extract-pdf", self.ollama_api_url))
-            .json(&serde_json::json!({ "pdf_path": pdf_path }))
-            .send()
-            .context("Failed to send request to Ollama API for PDF extraction")?;
-
-        let response_text = response
-            .text()
-            .context("Failed to read response from Ollama API")?;
-
-        let content: Value = serde_json::from_str(&response_text)
-            .context("Failed to parse JSON response from Ollama API")?;
+    /// Sends the raw embedding request to the Ollama API without dimension validation.
+    /// Used directly by [`OllamaMCP::new`] to probe a model's dimensionality before
+    /// that dimensionality is known.
+    fn generate_embedding_raw(&self, content: &str) -> error::Result<Vec<f32>> {
+        retry::with_backoff(self.embedder.max_attempts, self.embedder.base_delay, || {
+            let client = Client::new();
+            let response = client
+                .post(format!("{}/generate-embedding", self.ollama_api_url))
+                .header("Authorization", format!("Bearer {}", self.ollama_api_key))
+                .json(&serde_json::json!({ "model": self.embedder.model_name, "content": content }))
+                .send()
+                .map_err(|_| Error::ServerUnreachable)?;
+
+            if !response.status().is_success() {
+                return Err(error::from_status(response.status(), &self.embedder.model_name));
+            }
+
+            let response_text = response.text().map_err(|_| Error::ServerUnreachable)?;
+            let content: Value = serde_json::from_str(&response_text)?;
+
+            content["embedding"]
+                .as_array()
+                .map(|arr| arr.iter().filter_map(|v| v.as_f64().map(|f| f as f32)).collect())
+                .ok_or(Error::MissingField { field: "embedding" })
+        })
+    }
 
-        content["text"]
-            .as_str()
-            .map(|s| s.to_string())
-            .context("No 'text' field in Ollama API response")
+    /// Sends a query to the Ollama API and retrieves the response, retrying
+    /// transient failures with exponential backoff.
+    fn query_ollama(&self, query: &str) -> error::Result<String> {
+        retry::with_backoff(self.embedder.max_attempts, self.embedder.base_delay, || {
+            let client = Client::new();
+            let response = client
+                .post(format!("{}/query", self.ollama_api_url))
+                .header("Authorization", format!("Bearer {}", self.ollama_api_key))
+                .json(&serde_json::json!({ "query": query, "num_ctx": self.embedder.num_ctx }))
+                .send()
+                .map_err(|_| Error::ServerUnreachable)?;
+
+            if !response.status().is_success() {
+                return Err(error::from_status(response.status(), &self.embedder.model_name));
+            }
+
+            let response_text = response.text().map_err(|_| Error::ServerUnreachable)?;
+            let content: Value = serde_json::from_str(&response_text)?;
+
+            content["result"]
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or(Error::MissingField { field: "result" })
+        })
     }
+}
 
+impl Backend for OllamaMCP {
     fn generate_embedding(&self, content: &str) -> Result<Vec<f32>> {
-        """
-        Generates an embedding for the given content using the Ollama API.
-
-        Input:
-        - content: The content for which to generate the embedding.
-        Output:
-        - Result: Ok with the generated embedding as a vector of f32, Err if there was an error.
-
-        Note: The embedding is generated by sending a request to the Ollama API.
-        """
-        let client = Client::new();
-        let response = client
-            .post(format!("{}/generate-embedding", self.ollama_api_url))
-            .header("Authorization", format!("Bearer {}", self.ollama_api_key))
-            .json(&serde_json::json!({ "content": content }))
-            .send()
-            .context("Failed to send request to Ollama API for embedding generation")?;
-
-        let response_text = response
-            .text()
-            .context("Failed to read response from Ollama API")?;
-
-        let content: Value = serde_json::from_str(&response_text)
-            .context("Failed to parse JSON response from Ollama API")?;
-
-        content["embedding"]
-            .as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_f64().map(|f| f as f32)).collect())
-            .context("No 'embedding' field in Ollama API response")
+        OllamaMCP::generate_embedding(self, content)
     }
 
-    fn query_ollama(&self, query: &str) -> Result<String> {
-        """
-        Sends a query to the Ollama API and retrieves the response.
-
-        Input:
-        - query: The query string to be sent to the Ollama API.
-        Output:
-        - Result: Ok with the response from the Ollama API, Err if there was an error.
-
-        Note: The query is sent to the Ollama API, and the response is processed.
-        """
-        let client = Client::new();
-        let response = client
-            .post(format!("{}/query", self.ollama_api_url))
-            .header("Authorization", format!("Bearer {}", self.ollama_api_key))
-            .json(&serde_json::json!({ "query": query }))
-            .send()
-            .context("Failed to send query to Ollama API")?;
-
-        let response_text = response
-            .text()
-            .context("Failed to read response from Ollama API")?;
+    fn query(&self, query: &str) -> Result<String> {
+        self.query_ollama(query).map_err(Into::into)
+    }
 
-        let content: Value = serde_json::from_str(&response_text)
-            .context("Failed to parse JSON response from Ollama API")?;
+    fn extract_text(&self, path: &str) -> Result<String> {
+        self.extract_text_from_pdf(path).map_err(Into::into)
+    }
 
-        content["result"]
-            .as_str()
-            .map(|s| s.to_string())
-            .context("No 'result' field in Ollama API response")
+    fn preferred_thread_count(&self) -> Option<usize> {
+        Some(self.embedder.thread_count)
     }
-}
\ No newline at end of file
+}
@@ -1,40 +1,41 @@
 mod utils;
 
-pub use crate::utils::gemini::RAGSystem;
+pub use crate::utils::gemini::GeminiBackend;
 pub use ic_cdk_macros::query;
-
-//ic query for RAGSystem
-#[query]
-fn rag_system() -> RAGSystem {
-    RAGSystem::new()
+pub use ollama_mcp::RAGSystem;
+
+// Pre-Backend-refactor versions of this file exposed a `rag_system() -> RAGSystem`
+// IC query alongside the ones below. `RAGSystem<B>` holds a live `GeminiBackend`
+// (its API key) and isn't `CandidType`, so it was never actually a valid IC query
+// return type; that endpoint is not provided here. `clean_text`, which had no such
+// problem, is kept below and delegates to `ollama_mcp::clean_text`.
+fn new_rag_system() -> RAGSystem<GeminiBackend> {
+    RAGSystem::new(GeminiBackend::from_env().expect("GEMINI_API_KEY must be set"))
 }
 
 //ic query for adding a document to RAGSystem
 #[query]
-fn add_document(doc_id: String, content: String) {
-    let mut rag_system = RAGSystem::new();
-    rag_system.add_document(&doc_id, &content);
+fn add_document(doc_id: String, content: String) -> Result<(), String> {
+    let mut rag_system = new_rag_system();
+    rag_system.add_document(&doc_id, &content).map_err(|e| e.to_string())
 }
 
 //ic query for adding a pdf document to RAGSystem
 #[query]
-fn add_pdf_document(pdf_path: String, output_path: String) {
-    let mut rag_system = RAGSystem::new();
-    rag_system.add_pdf_document(&pdf_path, &output_path);
+fn add_pdf_document(pdf_path: String, output_path: String) -> Result<(), String> {
+    let mut rag_system = new_rag_system();
+    rag_system.add_pdf_document(&pdf_path, &output_path).map_err(|e| e.to_string())
 }
 
 //ic query for retrieving a document from RAGSystem
-
 #[query]
 fn retrieve(query: String) -> String {
-    let rag_system = RAGSystem::new();
+    let rag_system = new_rag_system();
     rag_system.retrieve(&query).unwrap()
 }
 
 //ic query for cleaning text
 #[query]
 fn clean_text(content: String) -> String {
-    let rag_system = RAGSystem::new();
-    rag_system.clean_text(&content)
+    ollama_mcp::clean_text(&content)
 }
-